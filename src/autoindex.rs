@@ -0,0 +1,227 @@
+//! Edge-rendered directory listings for prefix ("folder") requests.
+//!
+//! When a request path ends in `/`, there's no single object to serve. Instead a
+//! `ListObjectsV2`-style request goes to the resolved `Origin`; this module builds
+//! its query string, parses the resulting XML, and renders a plain HTML directory
+//! listing — no client-side JS. Sending the request is left to the caller.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::config::Origin;
+use crate::sign::uri_encode;
+
+/// Content-path prefixes that opt out of autoindexing entirely (e.g. games, which
+/// shouldn't expose a browsable file list).
+const AUTOINDEX_DISABLED_PREFIXES: &[&str] = &["games/"];
+
+/// Whether `path` (a request path with a trailing `/`) should get a rendered
+/// directory listing.
+pub fn autoindex_enabled(path: &str) -> bool {
+    !AUTOINDEX_DISABLED_PREFIXES.iter().any(|prefix| path.starts_with(prefix))
+}
+
+/// An object returned under `<Contents>` in a `ListObjectsV2` response.
+pub struct FileEntry {
+    pub key: String,
+    pub size: u64,
+    pub last_modified: String,
+}
+
+/// A parsed `ListObjectsV2` response: objects directly under the prefix, plus
+/// "subfolders" (the `<CommonPrefixes>` produced by `delimiter=/`).
+pub struct Listing {
+    pub files: Vec<FileEntry>,
+    pub subfolders: Vec<String>,
+}
+
+/// Builds the `ListObjectsV2` query string (without a leading `?`) for `prefix`,
+/// e.g. `list-type=2&prefix=games%2F&delimiter=%2F`. Callers attach this to a request
+/// for `origin.bucket_host` (and sign it via `sign::sign_request_if_configured` if
+/// the backend requires auth).
+pub fn list_query_string(prefix: &str) -> String {
+    format!("list-type=2&prefix={}&delimiter=%2F", uri_encode(prefix))
+}
+
+lazy_static! {
+    static ref CONTENTS_RE: Regex = Regex::new(r"(?s)<Contents>(.*?)</Contents>").unwrap();
+    static ref COMMON_PREFIX_RE: Regex =
+        Regex::new(r"(?s)<CommonPrefixes>\s*<Prefix>(.*?)</Prefix>\s*</CommonPrefixes>").unwrap();
+    static ref KEY_RE: Regex = Regex::new(r"(?s)<Key>(.*?)</Key>").unwrap();
+    static ref SIZE_RE: Regex = Regex::new(r"(?s)<Size>(.*?)</Size>").unwrap();
+    static ref LAST_MODIFIED_RE: Regex = Regex::new(r"(?s)<LastModified>(.*?)</LastModified>").unwrap();
+}
+
+/// Parses a `ListObjectsV2` XML response body into a `Listing`.
+pub fn parse_listing(xml: &str) -> Listing {
+    let files = CONTENTS_RE
+        .captures_iter(xml)
+        .map(|contents| {
+            let block = &contents[1];
+            FileEntry {
+                key: KEY_RE.captures(block).map(|c| c[1].to_string()).unwrap_or_default(),
+                size: SIZE_RE
+                    .captures(block)
+                    .and_then(|c| c[1].parse().ok())
+                    .unwrap_or(0),
+                last_modified: LAST_MODIFIED_RE
+                    .captures(block)
+                    .map(|c| c[1].to_string())
+                    .unwrap_or_default(),
+            }
+        })
+        .collect();
+
+    let subfolders = COMMON_PREFIX_RE
+        .captures_iter(xml)
+        .map(|c| c[1].to_string())
+        .collect();
+
+    Listing { files, subfolders }
+}
+
+/// Formats a byte count as a human-readable size, e.g. `1.5 MB`.
+pub fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{bytes} {}", UNITS[0]);
+    }
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+/// Escapes `&`, `<`, `>` and `"` so `s` is safe to interpolate into HTML text or
+/// into a double-quoted attribute. Every value rendered by `render_html` comes
+/// from the request path or from bucket object keys, both attacker-controlled, so
+/// nothing gets interpolated unescaped.
+fn escape_html(s: &str) -> String {
+    s.chars().fold(String::with_capacity(s.len()), |mut out, c| {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+        out
+    })
+}
+
+/// Renders `listing` for `path` (the request path, with trailing `/`) as a plain
+/// HTML directory listing with parent-folder navigation.
+pub fn render_html(path: &str, listing: &Listing) -> String {
+    let escaped_path = escape_html(path);
+    let mut rows = String::new();
+
+    if let Some(parent) = parent_path(path) {
+        rows.push_str(&format!(
+            "<tr><td><a href=\"/{}\">..</a></td><td></td><td></td></tr>\n",
+            escape_html(&parent)
+        ));
+    }
+
+    for prefix in &listing.subfolders {
+        let name = prefix.strip_prefix(path).unwrap_or(prefix).trim_end_matches('/');
+        rows.push_str(&format!(
+            "<tr><td><a href=\"/{}\">{}/</a></td><td></td><td></td></tr>\n",
+            escape_html(prefix),
+            escape_html(name)
+        ));
+    }
+
+    for file in &listing.files {
+        if file.key == path {
+            continue; // the folder's own placeholder object, not a listable child
+        }
+        let name = file.key.strip_prefix(path).unwrap_or(&file.key);
+        rows.push_str(&format!(
+            "<tr><td><a href=\"/{}\">{}</a></td><td>{}</td><td>{}</td></tr>\n",
+            escape_html(&file.key),
+            escape_html(name),
+            human_size(file.size),
+            escape_html(&file.last_modified)
+        ));
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head><title>Index of /{escaped_path}</title></head>\n<body>\n\
+         <h1>Index of /{escaped_path}</h1>\n<table>\n<tr><th>Name</th><th>Size</th><th>Last Modified</th></tr>\n\
+         {rows}</table>\n</body>\n</html>\n"
+    )
+}
+
+/// The parent "folder" of `path`, or `None` if `path` is the root or a top-level
+/// folder such as `games/` (no link back to the root is rendered).
+fn parent_path(path: &str) -> Option<String> {
+    let trimmed = path.trim_end_matches('/');
+    let idx = trimmed.rfind('/')?;
+    Some(format!("{}/", &trimmed[..idx]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_html_neutralizes_markup() {
+        assert_eq!(
+            escape_html(r#""><script>alert(1)</script>"#),
+            "&quot;&gt;&lt;script&gt;alert(1)&lt;/script&gt;"
+        );
+        assert_eq!(escape_html("plain-name.txt"), "plain-name.txt");
+    }
+
+    #[test]
+    fn render_html_escapes_attacker_controlled_key() {
+        let listing = Listing {
+            files: vec![FileEntry {
+                key: r#"games/"><script>alert(1)</script>"#.to_string(),
+                size: 10,
+                last_modified: "2024-01-01T00:00:00Z".to_string(),
+            }],
+            subfolders: vec![],
+        };
+
+        let html = render_html("games/", &listing);
+
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("&lt;script&gt;"));
+    }
+
+    #[test]
+    fn human_size_formats_units() {
+        assert_eq!(human_size(0), "0 B");
+        assert_eq!(human_size(1023), "1023 B");
+        assert_eq!(human_size(1536), "1.5 KB");
+        assert_eq!(human_size(1024 * 1024 * 3), "3.0 MB");
+    }
+
+    #[test]
+    fn parse_listing_extracts_files_and_subfolders() {
+        let xml = r#"
+            <ListBucketResult>
+                <Contents>
+                    <Key>games/readme.txt</Key>
+                    <Size>42</Size>
+                    <LastModified>2024-01-01T00:00:00.000Z</LastModified>
+                </Contents>
+                <CommonPrefixes>
+                    <Prefix>games/expansion/</Prefix>
+                </CommonPrefixes>
+            </ListBucketResult>
+        "#;
+
+        let listing = parse_listing(xml);
+
+        assert_eq!(listing.files.len(), 1);
+        assert_eq!(listing.files[0].key, "games/readme.txt");
+        assert_eq!(listing.files[0].size, 42);
+        assert_eq!(listing.subfolders, vec!["games/expansion/".to_string()]);
+    }
+}
@@ -1,18 +1,24 @@
 use lazy_static::lazy_static;
 use regex::Regex;
+use std::borrow::Cow;
 use std::collections::HashMap;
 
 /// Default edge server code - used when running on the local test server
 pub(crate) const DEFAULT_POP: &str = "SJC";
 
+/// `bucket_name`/`bucket_host`/`backend_name` are `Cow` rather than `&'static str`
+/// so that `origin_store::resolve` can substitute owned values read from the
+/// `origins` edge dictionary at request time, while the compiled-in constants
+/// below stay zero-allocation `Cow::Borrowed`s when the dictionary has no override.
+#[derive(Clone)]
 pub struct Origin {
-    /// This should match the name of a storage backend. See the the `Hosts` 
+    /// This should match the name of a storage backend. See the the `Hosts`
     /// section of the Fastly WASM service UI for more information.
-    pub backend_name: &'static str,
+    pub backend_name: Cow<'static, str>,
     /// The name of the bucket to serve content from.
-    pub bucket_name: &'static str,
+    pub bucket_name: Cow<'static, str>,
     /// The host that the bucket is served on. This is used to make requests to the backend.
-    pub bucket_host: &'static str,
+    pub bucket_host: Cow<'static, str>,
 }
 
 /// Details of the origins. You must edit the bucket_names and bucket_hosts. Do not change
@@ -20,119 +26,241 @@ pub struct Origin {
 
 // Images Cache Buckets
 pub(crate) const EU_IMAGES_ORIGIN: Origin = Origin {
-    backend_name: "eu_origin",
-    bucket_name: "images-shobl-cache",
-    bucket_host: "s3.eu-central-003.backblazeb2.com",
+    backend_name: Cow::Borrowed("eu_origin"),
+    bucket_name: Cow::Borrowed("images-shobl-cache"),
+    bucket_host: Cow::Borrowed("s3.eu-central-003.backblazeb2.com"),
 };
 
 pub(crate) const US_IMAGES_ORIGIN: Origin = Origin {
-    backend_name: "us_origin",
-    bucket_name: "images-shobl-cache-us",
-    bucket_host: "s3.us-west-004.backblazeb2.com",
+    backend_name: Cow::Borrowed("us_origin"),
+    bucket_name: Cow::Borrowed("images-shobl-cache-us"),
+    bucket_host: Cow::Borrowed("s3.us-west-004.backblazeb2.com"),
 };
 
 // Games Buckets
 pub(crate) const EU_GAMES_ORIGIN: Origin = Origin {
-    backend_name: "eu_origin",
-    bucket_name: "games-shobl",
-    bucket_host: "s3.eu-central-003.backblazeb2.com",
+    backend_name: Cow::Borrowed("eu_origin"),
+    bucket_name: Cow::Borrowed("games-shobl"),
+    bucket_host: Cow::Borrowed("s3.eu-central-003.backblazeb2.com"),
 };
 
 pub(crate) const US_GAMES_ORIGIN: Origin = Origin {
-    backend_name: "us_origin",
-    bucket_name: "games-shobl-us",
-    bucket_host: "s3.us-west-004.backblazeb2.com",
+    backend_name: Cow::Borrowed("us_origin"),
+    bucket_name: Cow::Borrowed("games-shobl-us"),
+    bucket_host: Cow::Borrowed("s3.us-west-004.backblazeb2.com"),
 };
 
 // Music Buckets
 pub(crate) const EU_MUSIC_ORIGIN: Origin = Origin {
-    backend_name: "eu_origin",
-    bucket_name: "music-shobl",
-    bucket_host: "s3.eu-central-003.backblazeb2.com",
+    backend_name: Cow::Borrowed("eu_origin"),
+    bucket_name: Cow::Borrowed("music-shobl"),
+    bucket_host: Cow::Borrowed("s3.eu-central-003.backblazeb2.com"),
 };
 
 pub(crate) const US_MUSIC_ORIGIN: Origin = Origin {
-    backend_name: "us_origin",
-    bucket_name: "music-shobl-us",
-    bucket_host: "s3.us-west-004.backblazeb2.com",
+    backend_name: Cow::Borrowed("us_origin"),
+    bucket_name: Cow::Borrowed("music-shobl-us"),
+    bucket_host: Cow::Borrowed("s3.us-west-004.backblazeb2.com"),
 };
 
 // Comics Buckets
 pub(crate) const EU_COMICS_ORIGIN: Origin = Origin {
-    backend_name: "eu_origin",
-    bucket_name: "comics-shobl",
-    bucket_host: "s3.eu-central-003.backblazeb2.com",
+    backend_name: Cow::Borrowed("eu_origin"),
+    bucket_name: Cow::Borrowed("comics-shobl"),
+    bucket_host: Cow::Borrowed("s3.eu-central-003.backblazeb2.com"),
 };
 
 pub(crate) const US_COMICS_ORIGIN: Origin = Origin {
-    backend_name: "us_origin",
-    bucket_name: "comics-shobl-us",
-    bucket_host: "s3.us-west-004.backblazeb2.com",
+    backend_name: Cow::Borrowed("us_origin"),
+    bucket_name: Cow::Borrowed("comics-shobl-us"),
+    bucket_host: Cow::Borrowed("s3.us-west-004.backblazeb2.com"),
 };
 
 // Videos Buckets
 pub(crate) const EU_VIDEOS_ORIGIN: Origin = Origin {
-    backend_name: "eu_origin",
-    bucket_name: "videos-shobl",
-    bucket_host: "s3.eu-central-003.backblazeb2.com",
+    backend_name: Cow::Borrowed("eu_origin"),
+    bucket_name: Cow::Borrowed("videos-shobl"),
+    bucket_host: Cow::Borrowed("s3.eu-central-003.backblazeb2.com"),
 };
 
 pub(crate) const US_VIDEOS_ORIGIN: Origin = Origin {
-    backend_name: "us_origin",
-    bucket_name: "videos-shobl-us",
-    bucket_host: "s3.us-west-004.backblazeb2.com",
+    backend_name: Cow::Borrowed("us_origin"),
+    bucket_name: Cow::Borrowed("videos-shobl-us"),
+    bucket_host: Cow::Borrowed("s3.us-west-004.backblazeb2.com"),
 };
 
 // Art Buckets
 pub(crate) const EU_ART_ORIGIN: Origin = Origin {
-    backend_name: "eu_origin",
-    bucket_name: "art-shobl",
-    bucket_host: "s3.eu-central-003.backblazeb2.com",
+    backend_name: Cow::Borrowed("eu_origin"),
+    bucket_name: Cow::Borrowed("art-shobl"),
+    bucket_host: Cow::Borrowed("s3.eu-central-003.backblazeb2.com"),
 };
 
 pub(crate) const US_ART_ORIGIN: Origin = Origin {
-    backend_name: "us_origin",
-    bucket_name: "art-shobl-us",
-    bucket_host: "s3.us-west-004.backblazeb2.com",
+    backend_name: Cow::Borrowed("us_origin"),
+    bucket_name: Cow::Borrowed("art-shobl-us"),
+    bucket_host: Cow::Borrowed("s3.us-west-004.backblazeb2.com"),
 };
 
 // Public SEO Images Buckets
 pub(crate) const EU_PUBLIC_IMAGES_ORIGIN: Origin = Origin {
-    backend_name: "eu_origin",
-    bucket_name: "images-public-seo",
-    bucket_host: "s3.eu-central-003.backblazeb2.com",
+    backend_name: Cow::Borrowed("eu_origin"),
+    bucket_name: Cow::Borrowed("images-public-seo"),
+    bucket_host: Cow::Borrowed("s3.eu-central-003.backblazeb2.com"),
 };
 
 pub(crate) const US_PUBLIC_IMAGES_ORIGIN: Origin = Origin {
-    backend_name: "us_origin",
-    bucket_name: "images-public-seo-us",
-    bucket_host: "s3.us-west-004.backblazeb2.com",
+    backend_name: Cow::Borrowed("us_origin"),
+    bucket_name: Cow::Borrowed("images-public-seo-us"),
+    bucket_host: Cow::Borrowed("s3.us-west-004.backblazeb2.com"),
+};
+
+// APAC Buckets
+pub(crate) const AP_IMAGES_ORIGIN: Origin = Origin {
+    backend_name: Cow::Borrowed("ap_origin"),
+    bucket_name: Cow::Borrowed("images-shobl-cache-ap"),
+    bucket_host: Cow::Borrowed("s3.ap-southeast-002.backblazeb2.com"),
+};
+
+pub(crate) const AP_GAMES_ORIGIN: Origin = Origin {
+    backend_name: Cow::Borrowed("ap_origin"),
+    bucket_name: Cow::Borrowed("games-shobl-ap"),
+    bucket_host: Cow::Borrowed("s3.ap-southeast-002.backblazeb2.com"),
+};
+
+pub(crate) const AP_MUSIC_ORIGIN: Origin = Origin {
+    backend_name: Cow::Borrowed("ap_origin"),
+    bucket_name: Cow::Borrowed("music-shobl-ap"),
+    bucket_host: Cow::Borrowed("s3.ap-southeast-002.backblazeb2.com"),
+};
+
+pub(crate) const AP_COMICS_ORIGIN: Origin = Origin {
+    backend_name: Cow::Borrowed("ap_origin"),
+    bucket_name: Cow::Borrowed("comics-shobl-ap"),
+    bucket_host: Cow::Borrowed("s3.ap-southeast-002.backblazeb2.com"),
+};
+
+pub(crate) const AP_VIDEOS_ORIGIN: Origin = Origin {
+    backend_name: Cow::Borrowed("ap_origin"),
+    bucket_name: Cow::Borrowed("videos-shobl-ap"),
+    bucket_host: Cow::Borrowed("s3.ap-southeast-002.backblazeb2.com"),
+};
+
+pub(crate) const AP_ART_ORIGIN: Origin = Origin {
+    backend_name: Cow::Borrowed("ap_origin"),
+    bucket_name: Cow::Borrowed("art-shobl-ap"),
+    bucket_host: Cow::Borrowed("s3.ap-southeast-002.backblazeb2.com"),
+};
+
+pub(crate) const AP_PUBLIC_IMAGES_ORIGIN: Origin = Origin {
+    backend_name: Cow::Borrowed("ap_origin"),
+    bucket_name: Cow::Borrowed("images-public-seo-ap"),
+    bucket_host: Cow::Borrowed("s3.ap-southeast-002.backblazeb2.com"),
 };
 
 // Default origins (using images cache for backward compatibility)
 pub(crate) const EU_ORIGIN: Origin = EU_IMAGES_ORIGIN;
 pub(crate) const US_ORIGIN: Origin = US_IMAGES_ORIGIN;
+pub(crate) const AP_ORIGIN: Origin = AP_IMAGES_ORIGIN;
+
+/// The known regions. The order matters: `region_index` maps a region to its
+/// position here, which indexes the per-content-type `*_ORIGINS` arrays below.
+/// Adding a region means adding its name here, its `<REGION>_*_ORIGIN` constants
+/// above (and to each `*_ORIGINS` array), an arm in `fallback_regions`, and its
+/// POPs to `POP_REGION` below.
+pub(crate) const REGIONS: [&str; 3] = ["eu", "us", "ap"];
+
+/// Per-region fallback order used when building an `OriginChain`: which other
+/// regions to retry against, nearest first, if the region-local bucket fails.
+fn fallback_regions(region: &str) -> &'static [&'static str] {
+    match region {
+        "eu" => &["us", "ap"],
+        "ap" => &["us", "eu"],
+        _ => &["eu", "ap"],
+    }
+}
+
+fn region_index(region: &str) -> usize {
+    REGIONS.iter().position(|&r| r == region).unwrap_or(1) // default to "us"
+}
+
+/// EU/US/AP triples per content type, indexed by `region_index`. These feed
+/// `get_origin_for_content_path`, which orders the chain as the region-local
+/// bucket first, then its fallback regions per `fallback_regions`.
+pub(crate) const IMAGES_ORIGINS: [Origin; 3] = [EU_IMAGES_ORIGIN, US_IMAGES_ORIGIN, AP_IMAGES_ORIGIN];
+pub(crate) const GAMES_ORIGINS: [Origin; 3] = [EU_GAMES_ORIGIN, US_GAMES_ORIGIN, AP_GAMES_ORIGIN];
+pub(crate) const ART_ORIGINS: [Origin; 3] = [EU_ART_ORIGIN, US_ART_ORIGIN, AP_ART_ORIGIN];
+pub(crate) const MUSIC_ORIGINS: [Origin; 3] = [EU_MUSIC_ORIGIN, US_MUSIC_ORIGIN, AP_MUSIC_ORIGIN];
+pub(crate) const VIDEOS_ORIGINS: [Origin; 3] = [EU_VIDEOS_ORIGIN, US_VIDEOS_ORIGIN, AP_VIDEOS_ORIGIN];
+pub(crate) const COMICS_ORIGINS: [Origin; 3] = [EU_COMICS_ORIGIN, US_COMICS_ORIGIN, AP_COMICS_ORIGIN];
+pub(crate) const PUBLIC_IMAGES_ORIGINS: [Origin; 3] =
+    [EU_PUBLIC_IMAGES_ORIGIN, US_PUBLIC_IMAGES_ORIGIN, AP_PUBLIC_IMAGES_ORIGIN];
+
+/// An ordered list of origins to try for a single request: the region-local bucket
+/// first, then fallbacks to retry against when the local one errors out. Origins
+/// are owned (not `&'static`) since `origin_store::resolve` may substitute values
+/// read from the `origins` edge dictionary at request time.
+#[derive(Clone)]
+pub struct OriginChain {
+    origins: Vec<Origin>,
+}
+
+impl OriginChain {
+    fn new(origins: Vec<Origin>) -> Self {
+        Self { origins }
+    }
+
+    /// The region-local origin; the first one a caller should try.
+    pub fn primary(&self) -> &Origin {
+        &self.origins[0]
+    }
 
-/// Content-type based origin routing
-/// Routes requests based on the file path prefix (e.g., /games/, /art/, /music/)
-pub fn get_origin_for_content_path(path: &str, region: &str) -> Origin {
-    let origin = if path.starts_with("games/") {
-        if region == "eu" { EU_GAMES_ORIGIN } else { US_GAMES_ORIGIN }
+    pub fn iter(&self) -> std::slice::Iter<'_, Origin> {
+        self.origins.iter()
+    }
+}
+
+/// Builds the chain for a content-type's per-region triple: the region-local
+/// bucket first, then its fallback regions (per `fallback_regions`), then the
+/// region-local default image cache (unless that's already in the chain). Each
+/// entry is passed through `origin_store::resolve` so a runtime override in the
+/// `origins` edge dictionary takes precedence over the compiled default.
+fn chain_for_regions(triple: &'static [Origin; 3], region: &str, content_type: &str) -> OriginChain {
+    let local_idx = region_index(region);
+    let resolve = |idx: usize, region: &str| crate::origin_store::resolve(&triple[idx], region, content_type);
+
+    let mut origins = vec![resolve(local_idx, region)];
+    for &fallback in fallback_regions(region) {
+        origins.push(resolve(region_index(fallback), fallback));
+    }
+
+    if content_type != "images" {
+        origins.push(crate::origin_store::resolve(&IMAGES_ORIGINS[local_idx], region, "images"));
+    }
+    OriginChain::new(origins)
+}
+
+/// Content-type based origin routing.
+/// Routes requests based on the file path prefix (e.g., /games/, /art/, /music/),
+/// returning an ordered `OriginChain` to retry against on failure. `region` is
+/// one of `REGIONS` ("eu", "us", "ap").
+pub fn get_origin_for_content_path(path: &str, region: &str) -> OriginChain {
+    if path.starts_with("games/") {
+        chain_for_regions(&GAMES_ORIGINS, region, "games")
     } else if path.starts_with("art/") {
-        if region == "eu" { EU_ART_ORIGIN } else { US_ART_ORIGIN }
+        chain_for_regions(&ART_ORIGINS, region, "art")
     } else if path.starts_with("music/") || path.starts_with("audio/") {
-        if region == "eu" { EU_MUSIC_ORIGIN } else { US_MUSIC_ORIGIN }
+        chain_for_regions(&MUSIC_ORIGINS, region, "music")
     } else if path.starts_with("videos/") || path.starts_with("video/") {
-        if region == "eu" { EU_VIDEOS_ORIGIN } else { US_VIDEOS_ORIGIN }
+        chain_for_regions(&VIDEOS_ORIGINS, region, "videos")
     } else if path.starts_with("comics/") {
-        if region == "eu" { EU_COMICS_ORIGIN } else { US_COMICS_ORIGIN }
+        chain_for_regions(&COMICS_ORIGINS, region, "comics")
     } else if path.starts_with("images-public/") {
-        if region == "eu" { EU_PUBLIC_IMAGES_ORIGIN } else { US_PUBLIC_IMAGES_ORIGIN }
+        chain_for_regions(&PUBLIC_IMAGES_ORIGINS, region, "images-public")
     } else {
         // Default to images cache for unknown types
-        if region == "eu" { EU_IMAGES_ORIGIN } else { US_IMAGES_ORIGIN }
-    };
-    origin
+        chain_for_regions(&IMAGES_ORIGINS, region, "images")
+    }
 }
 
 lazy_static! {
@@ -143,108 +271,122 @@ lazy_static! {
 // If auth is required, configure your access keys in an edge dictionary named "bucket_auth":
 // <backend_name>_access_key_id
 // <backend_name>_secret_access_key
+//
+// Requests to backends with credentials configured are signed with AWS SigV4 by
+// `sign::sign_request_if_configured` before dispatch; see sign.rs.
 
 lazy_static! {
-    /// Simple mapping from POP to origin:
-    /// North America, South America, Asia/Pacific => US
-    /// Europe, Africa => EU
-    pub(crate) static ref POP_ORIGIN: HashMap<&'static str, Origin> = HashMap::from([
-        ("AMS", EU_ORIGIN),
-        ("WDC", US_ORIGIN),
-        ("IAD", US_ORIGIN),
-        ("BWI", US_ORIGIN),
-        ("DCA", US_ORIGIN),
-        ("ATL", US_ORIGIN),
-        ("FTY", US_ORIGIN),
-        ("PDK", US_ORIGIN),
-        ("AKL", US_ORIGIN),
-        ("BOG", US_ORIGIN),
-        ("BOS", US_ORIGIN),
-        ("BNE", US_ORIGIN),
-        ("EZE", US_ORIGIN),
-        ("CPT", EU_ORIGIN),
-        ("MAA", US_ORIGIN),
-        ("ORD", US_ORIGIN),
-        ("LOT", US_ORIGIN),
-        ("CHI", US_ORIGIN),
-        ("MDW", US_ORIGIN),
-        ("PWK", US_ORIGIN),
-        ("CMH", US_ORIGIN),
-        ("LCK", US_ORIGIN),
-        ("CPH", EU_ORIGIN),
-        ("CWB", US_ORIGIN),
-        ("DFW", US_ORIGIN),
-        ("DAL", US_ORIGIN),
-        ("DEL", US_ORIGIN),
-        ("DEN", US_ORIGIN),
-        ("DTW", US_ORIGIN),
-        ("DXB", US_ORIGIN),
-        ("DUB", EU_ORIGIN),
-        ("FOR", US_ORIGIN),
-        ("FRA", EU_ORIGIN),
-        ("HHN", EU_ORIGIN),
-        ("FJR", US_ORIGIN),
-        ("GNV", US_ORIGIN),
-        ("ACC", EU_ORIGIN),
-        ("HEL", EU_ORIGIN),
-        ("HKG", US_ORIGIN),
-        ("HNL", US_ORIGIN),
-        ("IAH", US_ORIGIN),
-        ("HYD", US_ORIGIN),
-        ("JAX", US_ORIGIN),
-        ("JNB", EU_ORIGIN),
-        ("MCI", US_ORIGIN),
-        ("CCU", US_ORIGIN),
-        ("KUL", US_ORIGIN),
-        ("LIM", US_ORIGIN),
-        ("LCY", EU_ORIGIN),
-        ("LHR", EU_ORIGIN),
-        ("LON", EU_ORIGIN),
-        ("LGB", US_ORIGIN),
-        ("SMO", US_ORIGIN),
-        ("BUR", US_ORIGIN),
-        ("MAD", EU_ORIGIN),
-        ("MAN", EU_ORIGIN),
-        ("MNL", US_ORIGIN),
-        ("MRS", EU_ORIGIN),
-        ("MEL", US_ORIGIN),
-        ("MIA", US_ORIGIN),
-        ("MXP", EU_ORIGIN),
-        ("LIN", EU_ORIGIN),
-        ("MSP", US_ORIGIN),
-        ("STP", US_ORIGIN),
-        ("YUL", US_ORIGIN),
-        ("BOM", US_ORIGIN),
-        ("MUC", EU_ORIGIN),
-        ("LGA", US_ORIGIN),
-        ("EWR", US_ORIGIN),
-        ("ITM", US_ORIGIN),
-        ("OSL", EU_ORIGIN),
-        ("PAO", US_ORIGIN),
-        ("CDG", EU_ORIGIN),
-        ("PER", US_ORIGIN),
-        ("PHX", US_ORIGIN),
-        ("PDX", US_ORIGIN),
-        ("GIG", US_ORIGIN),
-        ("FCO", EU_ORIGIN),
-        ("SJC", US_ORIGIN),
-        ("SCL", US_ORIGIN),
-        ("CGH", US_ORIGIN),
-        ("GRU", US_ORIGIN),
-        ("SEA", US_ORIGIN),
-        ("BFI", US_ORIGIN),
-        ("ICN", US_ORIGIN),
-        ("QPG", US_ORIGIN),
-        ("SOF", EU_ORIGIN),
-        ("STL", US_ORIGIN),
-        ("BMA", EU_ORIGIN),
-        ("SYD", US_ORIGIN),
-        ("TYO", US_ORIGIN),
-        ("HND", US_ORIGIN),
-        ("NRT", US_ORIGIN),
-        ("YYZ", US_ORIGIN),
-        ("YVR", US_ORIGIN),
-        ("VIE", EU_ORIGIN),
-        ("WLG", US_ORIGIN),
+    /// POP -> nearest region. Data-driven so that adding a region (as with `ap`
+    /// here) or reassigning a POP never needs new branching logic, just a new
+    /// table entry.
+    pub(crate) static ref POP_REGION: HashMap<&'static str, &'static str> = HashMap::from([
+        ("AMS", "eu"),
+        ("WDC", "us"),
+        ("IAD", "us"),
+        ("BWI", "us"),
+        ("DCA", "us"),
+        ("ATL", "us"),
+        ("FTY", "us"),
+        ("PDK", "us"),
+        ("AKL", "ap"),
+        ("BOG", "us"),
+        ("BOS", "us"),
+        ("BNE", "ap"),
+        ("EZE", "us"),
+        ("CPT", "eu"),
+        ("MAA", "ap"),
+        ("ORD", "us"),
+        ("LOT", "us"),
+        ("CHI", "us"),
+        ("MDW", "us"),
+        ("PWK", "us"),
+        ("CMH", "us"),
+        ("LCK", "us"),
+        ("CPH", "eu"),
+        ("CWB", "us"),
+        ("DFW", "us"),
+        ("DAL", "us"),
+        ("DEL", "ap"),
+        ("DEN", "us"),
+        ("DTW", "us"),
+        ("DXB", "us"),
+        ("DUB", "eu"),
+        ("FOR", "us"),
+        ("FRA", "eu"),
+        ("HHN", "eu"),
+        ("FJR", "us"),
+        ("GNV", "us"),
+        ("ACC", "eu"),
+        ("HEL", "eu"),
+        ("HKG", "ap"),
+        ("HNL", "us"),
+        ("IAH", "us"),
+        ("HYD", "ap"),
+        ("JAX", "us"),
+        ("JNB", "eu"),
+        ("MCI", "us"),
+        ("CCU", "ap"),
+        ("KUL", "ap"),
+        ("LIM", "us"),
+        ("LCY", "eu"),
+        ("LHR", "eu"),
+        ("LON", "eu"),
+        ("LGB", "us"),
+        ("SMO", "us"),
+        ("BUR", "us"),
+        ("MAD", "eu"),
+        ("MAN", "eu"),
+        ("MNL", "ap"),
+        ("MRS", "eu"),
+        ("MEL", "ap"),
+        ("MIA", "us"),
+        ("MXP", "eu"),
+        ("LIN", "eu"),
+        ("MSP", "us"),
+        ("STP", "us"),
+        ("YUL", "us"),
+        ("BOM", "ap"),
+        ("MUC", "eu"),
+        ("LGA", "us"),
+        ("EWR", "us"),
+        ("ITM", "ap"),
+        ("OSL", "eu"),
+        ("PAO", "us"),
+        ("CDG", "eu"),
+        ("PER", "ap"),
+        ("PHX", "us"),
+        ("PDX", "us"),
+        ("GIG", "us"),
+        ("FCO", "eu"),
+        ("SJC", "us"),
+        ("SCL", "us"),
+        ("CGH", "us"),
+        ("GRU", "us"),
+        ("SEA", "us"),
+        ("BFI", "us"),
+        ("ICN", "ap"),
+        ("QPG", "ap"),
+        ("SOF", "eu"),
+        ("STL", "us"),
+        ("BMA", "eu"),
+        ("SYD", "ap"),
+        ("TYO", "ap"),
+        ("HND", "ap"),
+        ("NRT", "ap"),
+        ("YYZ", "us"),
+        ("YVR", "us"),
+        ("VIE", "eu"),
+        ("WLG", "ap"),
     ]);
 }
+
+/// Resolves the nearest region for a Fastly POP code, data-driven from
+/// `POP_REGION`. Falls back to `DEFAULT_POP`'s region, and ultimately `"us"`,
+/// for an unrecognized POP.
+pub fn nearest_region_for_pop(pop: &str) -> &'static str {
+    POP_REGION
+        .get(pop)
+        .or_else(|| POP_REGION.get(DEFAULT_POP))
+        .copied()
+        .unwrap_or("us")
+}
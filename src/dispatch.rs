@@ -0,0 +1,99 @@
+//! Request dispatch against an `OriginChain`, retrying the next origin on failure.
+
+use fastly::{Request, Response};
+
+use crate::config::OriginChain;
+use crate::sign::sign_request_if_configured;
+
+/// Issues `req` against each origin in `chain`, in order, until one returns a
+/// successful (non-5xx) response. An origin is considered failed — and the next
+/// one in the chain tried — when the backend connection itself fails (including
+/// timeouts) or the response status is a server error. Returns the first success,
+/// or the outcome of the last origin tried if every origin in the chain failed.
+pub fn fetch_with_fallback(req: &Request, chain: &OriginChain) -> Result<Response, fastly::Error> {
+    let attempts = chain.iter().map(|origin| {
+        let mut attempt = req.clone_with_body();
+        attempt.set_header("host", origin.bucket_host.as_ref());
+        sign_request_if_configured(&mut attempt, origin);
+        attempt.send(origin.backend_name.as_ref())
+    });
+
+    resolve_fallback_outcome(attempts, |resp| resp.get_status().is_server_error())
+        .expect("OriginChain is never empty")
+}
+
+/// What to do with the outcome of a single origin attempt.
+enum AttemptOutcome<T> {
+    /// Stop and use this result immediately.
+    Return(T),
+    /// Remember this as the best outcome so far and move on to the next origin.
+    KeepGoing(T),
+}
+
+/// Classifies the outcome of one origin attempt: a connection-level error or a
+/// server error (`is_server_error`) means move on to the next origin, anything
+/// else is returned immediately.
+fn classify_attempt<T, E>(
+    result: Result<T, E>,
+    is_server_error: impl FnOnce(&T) -> bool,
+) -> AttemptOutcome<Result<T, E>> {
+    match result {
+        Ok(resp) if is_server_error(&resp) => AttemptOutcome::KeepGoing(Ok(resp)),
+        Ok(resp) => AttemptOutcome::Return(Ok(resp)),
+        Err(err) => AttemptOutcome::KeepGoing(Err(err)),
+    }
+}
+
+/// Runs `attempts` through the same success/retry/give-up decision
+/// `fetch_with_fallback` uses: returns the first success, or the outcome of the
+/// last attempt if every attempt failed (`None` if `attempts` is empty). Pure
+/// and runtime-free, so the fallback logic is unit-testable without a live
+/// `Request::send`.
+fn resolve_fallback_outcome<T, E>(
+    attempts: impl IntoIterator<Item = Result<T, E>>,
+    is_server_error: impl Fn(&T) -> bool,
+) -> Option<Result<T, E>> {
+    let mut last_outcome = None;
+    for result in attempts {
+        match classify_attempt(result, &is_server_error) {
+            AttemptOutcome::Return(outcome) => return Some(outcome),
+            AttemptOutcome::KeepGoing(outcome) => last_outcome = Some(outcome),
+        }
+    }
+    last_outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_5xx(status: &u16) -> bool {
+        (500..600).contains(status)
+    }
+
+    #[test]
+    fn returns_first_success() {
+        let attempts: Vec<Result<u16, &str>> = vec![Err("timeout"), Ok(500), Ok(200), Ok(503)];
+        assert_eq!(resolve_fallback_outcome(attempts, is_5xx), Some(Ok(200)));
+    }
+
+    #[test]
+    fn returns_last_attempts_outcome_when_every_attempt_fails() {
+        // Regression test: an earlier connection error must not shadow a later
+        // origin's 5xx response - the chronologically last attempt wins.
+        let attempts: Vec<Result<u16, &str>> = vec![Err("timeout"), Ok(503)];
+        assert_eq!(resolve_fallback_outcome(attempts, is_5xx), Some(Ok(503)));
+    }
+
+    #[test]
+    fn returns_last_error_when_the_last_attempt_errors() {
+        let attempts: Vec<Result<u16, &str>> = vec![Ok(500), Err("connection reset")];
+        assert_eq!(resolve_fallback_outcome(attempts, is_5xx), Some(Err("connection reset")));
+    }
+
+    #[test]
+    fn empty_attempts_yield_none() {
+        let attempts: Vec<Result<u16, &str>> = vec![];
+        assert_eq!(resolve_fallback_outcome(attempts, is_5xx), None);
+    }
+}
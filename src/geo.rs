@@ -0,0 +1,117 @@
+//! Country-code based geo routing.
+//!
+//! `POP_REGION` is a hand-maintained table of Fastly POP codes and is brittle: it
+//! has to be updated by hand whenever Fastly adds or reassigns a POP. This module
+//! routes on `geoip.country_code` instead, which is finer-grained and needs no
+//! maintenance as POPs change. `POP_REGION` (via `nearest_region_for_pop`) and
+//! `DEFAULT_POP` are kept only as a fallback for when the country lookup is
+//! unavailable.
+
+use std::collections::HashMap;
+
+use fastly::http::Request;
+use lazy_static::lazy_static;
+
+use crate::config::{nearest_region_for_pop, DEFAULT_POP};
+
+/// Header checked before falling back to `geoip.country_code`, so routing can be
+/// exercised locally or in CI without a live POP/GeoIP database.
+pub(crate) const GEO_OVERRIDE_HEADER: &str = "geo_override";
+
+lazy_static! {
+    /// ISO 3166-1 alpha-2 country code -> region. Codes not listed here route to
+    /// `us`.
+    static ref COUNTRY_REGION: HashMap<&'static str, &'static str> = HashMap::from([
+        // Europe -> eu
+        ("AD", "eu"), ("AL", "eu"), ("AT", "eu"), ("AX", "eu"), ("BA", "eu"), ("BE", "eu"),
+        ("BG", "eu"), ("BY", "eu"), ("CH", "eu"), ("CY", "eu"), ("CZ", "eu"), ("DE", "eu"),
+        ("DK", "eu"), ("EE", "eu"), ("ES", "eu"), ("FI", "eu"), ("FO", "eu"), ("FR", "eu"),
+        ("GB", "eu"), ("GG", "eu"), ("GI", "eu"), ("GR", "eu"), ("HR", "eu"), ("HU", "eu"),
+        ("IE", "eu"), ("IM", "eu"), ("IS", "eu"), ("IT", "eu"), ("JE", "eu"), ("LI", "eu"),
+        ("LT", "eu"), ("LU", "eu"), ("LV", "eu"), ("MC", "eu"), ("MD", "eu"), ("ME", "eu"),
+        ("MK", "eu"), ("MT", "eu"), ("NL", "eu"), ("NO", "eu"), ("PL", "eu"), ("PT", "eu"),
+        ("RO", "eu"), ("RS", "eu"), ("RU", "eu"), ("SE", "eu"), ("SI", "eu"), ("SJ", "eu"),
+        ("SK", "eu"), ("SM", "eu"), ("UA", "eu"), ("VA", "eu"), ("XK", "eu"),
+        // Africa -> eu
+        ("AO", "eu"), ("BF", "eu"), ("BI", "eu"), ("BJ", "eu"), ("BW", "eu"), ("CD", "eu"),
+        ("CF", "eu"), ("CG", "eu"), ("CI", "eu"), ("CM", "eu"), ("CV", "eu"), ("DJ", "eu"),
+        ("DZ", "eu"), ("EG", "eu"), ("EH", "eu"), ("ER", "eu"), ("ET", "eu"), ("GA", "eu"),
+        ("GH", "eu"), ("GM", "eu"), ("GN", "eu"), ("GQ", "eu"), ("GW", "eu"), ("KE", "eu"),
+        ("KM", "eu"), ("LR", "eu"), ("LS", "eu"), ("LY", "eu"), ("MA", "eu"), ("MG", "eu"),
+        ("ML", "eu"), ("MR", "eu"), ("MU", "eu"), ("MW", "eu"), ("MZ", "eu"), ("NA", "eu"),
+        ("NE", "eu"), ("NG", "eu"), ("RW", "eu"), ("SC", "eu"), ("SD", "eu"), ("SL", "eu"),
+        ("SN", "eu"), ("SO", "eu"), ("SS", "eu"), ("ST", "eu"), ("SZ", "eu"), ("TD", "eu"),
+        ("TG", "eu"), ("TN", "eu"), ("TZ", "eu"), ("UG", "eu"), ("YT", "eu"), ("ZA", "eu"),
+        ("ZM", "eu"), ("ZW", "eu"),
+        // Asia-Pacific -> ap
+        ("JP", "ap"), ("KR", "ap"), ("CN", "ap"), ("HK", "ap"), ("MO", "ap"), ("TW", "ap"),
+        ("SG", "ap"), ("MY", "ap"), ("ID", "ap"), ("TH", "ap"), ("VN", "ap"), ("PH", "ap"),
+        ("KH", "ap"), ("LA", "ap"), ("MM", "ap"), ("BN", "ap"), ("TL", "ap"), ("MN", "ap"),
+        ("AU", "ap"), ("NZ", "ap"), ("PG", "ap"), ("FJ", "ap"), ("SB", "ap"), ("VU", "ap"),
+        ("WS", "ap"), ("TO", "ap"), ("KI", "ap"), ("FM", "ap"), ("MH", "ap"), ("NR", "ap"),
+        ("PW", "ap"), ("TV", "ap"), ("IN", "ap"), ("PK", "ap"), ("BD", "ap"), ("LK", "ap"),
+        ("NP", "ap"), ("BT", "ap"), ("MV", "ap"),
+    ]);
+}
+
+/// Resolves the routing region for a request. `country` (an ISO 3166-1 alpha-2
+/// code, from `geo_override` or `geoip.country_code`) is authoritative when
+/// present, routed through `COUNTRY_REGION` (codes not listed there default to
+/// `us`). When it's `None` — no GeoIP data, e.g. for requests that don't carry a
+/// resolvable client IP — falls back to `pop` via `nearest_region_for_pop`.
+pub fn resolve_region(country: Option<&str>, pop: &str) -> &'static str {
+    if let Some(country) = country {
+        let code = country.to_ascii_uppercase();
+        return COUNTRY_REGION.get(code.as_str()).copied().unwrap_or("us");
+    }
+
+    nearest_region_for_pop(pop)
+}
+
+/// Resolves the routing region for an incoming request: the `geo_override` header
+/// when present (for local/CI testing where there's no live POP or GeoIP data),
+/// otherwise `geoip.country_code` for the client IP.
+pub fn region_for_request(req: &Request) -> &'static str {
+    let country = req
+        .get_header_str(GEO_OVERRIDE_HEADER)
+        .map(|value| value.to_string())
+        .or_else(|| {
+            req.get_client_ip_addr()
+                .and_then(fastly::geo::geo_lookup)
+                .map(|geo| geo.country_code().to_string())
+        });
+
+    let pop = std::env::var("FASTLY_POP").unwrap_or_else(|_| DEFAULT_POP.to_string());
+    resolve_region(country.as_deref(), &pop)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_region_routes_eu_us_ap_by_country() {
+        assert_eq!(resolve_region(Some("DE"), "SJC"), "eu");
+        assert_eq!(resolve_region(Some("US"), "SJC"), "us");
+        assert_eq!(resolve_region(Some("JP"), "SJC"), "ap");
+        assert_eq!(resolve_region(Some("AU"), "SJC"), "ap");
+        assert_eq!(resolve_region(Some("IN"), "SJC"), "ap");
+    }
+
+    #[test]
+    fn resolve_region_is_case_insensitive() {
+        assert_eq!(resolve_region(Some("jp"), "SJC"), "ap");
+    }
+
+    #[test]
+    fn resolve_region_unknown_country_defaults_to_us() {
+        assert_eq!(resolve_region(Some("ZZ"), "SJC"), "us");
+    }
+
+    #[test]
+    fn resolve_region_falls_back_to_pop_when_no_country() {
+        assert_eq!(resolve_region(None, "NRT"), "ap");
+        assert_eq!(resolve_region(None, "LHR"), "eu");
+        assert_eq!(resolve_region(None, "SJC"), "us");
+    }
+}
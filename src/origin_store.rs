@@ -0,0 +1,40 @@
+//! Runtime origin overrides via the `origins` edge dictionary.
+//!
+//! Every origin used to be a hard-coded `const Origin`, so rotating a bucket or
+//! retargeting a content type meant recompiling and redeploying the WASM module.
+//! This module lets an operator override any field at the edge instead: keys are
+//! named `origin.<region>.<content_type>.<field>` (e.g.
+//! `origin.eu.games.bucket_name`), and any key that's absent falls back to the
+//! compiled default, so the dictionary only needs entries for what's actually
+//! being overridden.
+
+use std::borrow::Cow;
+
+use fastly::Dictionary;
+
+use crate::config::Origin;
+
+const ORIGINS_DICT: &str = "origins";
+
+/// Resolves the effective `Origin` for `region`/`content_type`, substituting any
+/// `origin.<region>.<content_type>.<field>` entries present in the `origins` edge
+/// dictionary over `default`.
+pub fn resolve(default: &Origin, region: &str, content_type: &str) -> Origin {
+    let dict = Dictionary::open(ORIGINS_DICT);
+    let key = |field: &str| format!("origin.{region}.{content_type}.{field}");
+
+    Origin {
+        backend_name: dict
+            .get(&key("backend_name"))
+            .map(Cow::Owned)
+            .unwrap_or_else(|| default.backend_name.clone()),
+        bucket_name: dict
+            .get(&key("bucket_name"))
+            .map(Cow::Owned)
+            .unwrap_or_else(|| default.bucket_name.clone()),
+        bucket_host: dict
+            .get(&key("bucket_host"))
+            .map(Cow::Owned)
+            .unwrap_or_else(|| default.bucket_host.clone()),
+    }
+}
@@ -0,0 +1,249 @@
+//! AWS Signature Version 4 request signing for authenticated Backblaze B2 origins.
+//!
+//! B2's S3-compatible API accepts standard SigV4 auth, so private buckets can be
+//! served the same way any other S3-compatible origin would be. Credentials are
+//! looked up per-backend from the `bucket_auth` edge dictionary (see `config.rs`)
+//! and a request is only signed when both keys for that backend are present.
+
+use chrono::Utc;
+use fastly::http::Request;
+use fastly::Dictionary;
+use hex;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+use crate::config::{Origin, REGION_REGEX};
+
+type HmacSha256 = Hmac<Sha256>;
+
+const BUCKET_AUTH_DICT: &str = "bucket_auth";
+
+/// Access key pair for a single backend, as stored in the `bucket_auth` edge dictionary.
+pub struct Credentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+}
+
+impl Credentials {
+    /// Looks up `<backend_name>_access_key_id` / `<backend_name>_secret_access_key`
+    /// in the `bucket_auth` edge dictionary. Returns `None` if either key is missing,
+    /// which callers treat as "this backend does not require signing".
+    pub fn lookup(backend_name: &str) -> Option<Self> {
+        let dict = Dictionary::open(BUCKET_AUTH_DICT);
+        let access_key_id = dict.get(&format!("{backend_name}_access_key_id"))?;
+        let secret_access_key = dict.get(&format!("{backend_name}_secret_access_key"))?;
+        Some(Self {
+            access_key_id,
+            secret_access_key,
+        })
+    }
+}
+
+/// Signs `req` for `origin` with SigV4 if credentials for `origin.backend_name` are
+/// configured in the `bucket_auth` edge dictionary. The region used in the
+/// credential scope is extracted from `origin.bucket_host` via `REGION_REGEX`.
+/// No-op when no credentials are configured for this backend.
+pub fn sign_request_if_configured(req: &mut Request, origin: &Origin) {
+    let Some(creds) = Credentials::lookup(&origin.backend_name) else {
+        return;
+    };
+    let Some(captures) = REGION_REGEX.captures(&origin.bucket_host) else {
+        eprintln!(
+            "sign_request_if_configured: credentials configured for backend \
+             '{}' but bucket_host '{}' doesn't match REGION_REGEX; request will \
+             be sent unsigned",
+            origin.backend_name, origin.bucket_host
+        );
+        return;
+    };
+    let region = captures[1].to_string();
+    sign_request(req, origin, &region, &creds);
+}
+
+/// Computes an AWS SigV4 `Authorization` header for `req` against `origin` and
+/// attaches it, along with the `x-amz-date` and `x-amz-content-sha256` headers
+/// that the signature covers.
+pub fn sign_request(req: &mut Request, origin: &Origin, region: &str, creds: &Credentials) {
+    let now = Utc::now();
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date_stamp = now.format("%Y%m%d").to_string();
+
+    let method = req.get_method_str().to_uppercase();
+    let canonical_uri = canonical_uri(req.get_path());
+    let canonical_query = canonical_query_string(req.get_query_str().unwrap_or(""));
+    let payload_hash = payload_hash(&method, req.get_body_str().as_deref());
+
+    let canonical_headers = format!(
+        "host:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n",
+        origin.bucket_host, payload_hash, amz_date
+    );
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+
+    let canonical_request = format!(
+        "{method}\n{canonical_uri}\n{canonical_query}\n{canonical_headers}\n{signed_headers}\n{payload_hash}"
+    );
+
+    let credential_scope = format!("{date_stamp}/{region}/s3/aws4_request");
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{amz_date}\n{credential_scope}\n{}",
+        sha256_hex(canonical_request.as_bytes())
+    );
+
+    let signing_key = signing_key(&creds.secret_access_key, &date_stamp, region);
+    let signature = hex::encode(hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+
+    let authorization = format!(
+        "AWS4-HMAC-SHA256 Credential={}/{credential_scope}, SignedHeaders={signed_headers}, Signature={signature}",
+        creds.access_key_id
+    );
+
+    req.set_header("Authorization", authorization);
+    req.set_header("x-amz-date", amz_date);
+    req.set_header("x-amz-content-sha256", payload_hash);
+    req.set_header("host", origin.bucket_host.as_ref());
+}
+
+/// `UNSIGNED-PAYLOAD` for bodyless/streamed requests (e.g. GETs), otherwise the
+/// lowercase-hex SHA256 of the body.
+fn payload_hash(method: &str, body: Option<&str>) -> String {
+    match body {
+        Some(body) if method != "GET" && method != "HEAD" => sha256_hex(body.as_bytes()),
+        _ => "UNSIGNED-PAYLOAD".to_string(),
+    }
+}
+
+/// URI-encodes each path segment individually, leaving the separating `/` alone.
+fn canonical_uri(path: &str) -> String {
+    if path.is_empty() {
+        return "/".to_string();
+    }
+    path.split('/')
+        .map(uri_encode)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Builds the canonical query string: URI-encoded `key=value` pairs sorted by key,
+/// then value, joined with `&`.
+fn canonical_query_string(query: &str) -> String {
+    if query.is_empty() {
+        return String::new();
+    }
+    let mut pairs: Vec<(String, String)> = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let mut parts = pair.splitn(2, '=');
+            let key = parts.next().unwrap_or("");
+            let value = parts.next().unwrap_or("");
+            (uri_encode(key), uri_encode(value))
+        })
+        .collect();
+    pairs.sort();
+    pairs
+        .into_iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// RFC 3986 unreserved-character percent-encoding, as required by SigV4.
+pub(crate) fn uri_encode(component: &str) -> String {
+    const UNRESERVED: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_.~";
+    component
+        .bytes()
+        .map(|byte| {
+            if UNRESERVED.contains(&byte) {
+                (byte as char).to_string()
+            } else {
+                format!("%{byte:02X}")
+            }
+        })
+        .collect()
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    hex::encode(hasher.finalize())
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts a key of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Derives the SigV4 signing key by chaining `kDate -> kRegion -> kService -> kSigning`.
+fn signing_key(secret_access_key: &str, date_stamp: &str, region: &str) -> Vec<u8> {
+    let k_date = hmac_sha256(format!("AWS4{secret_access_key}").as_bytes(), date_stamp.as_bytes());
+    let k_region = hmac_sha256(&k_date, region.as_bytes());
+    let k_service = hmac_sha256(&k_region, b"s3");
+    hmac_sha256(&k_service, b"aws4_request")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sha256_hex_matches_known_vectors() {
+        assert_eq!(
+            sha256_hex(b""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn hmac_sha256_matches_rfc4231_case_1() {
+        let key = [0x0bu8; 20];
+        let expected = "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7";
+        assert_eq!(hex::encode(hmac_sha256(&key, b"Hi There")), expected);
+    }
+
+    #[test]
+    fn signing_key_derives_aws_s3_sigv4_test_key() {
+        // Known-good AWS SigV4 example credentials (secret access key is AWS's
+        // published example key, not a real credential).
+        let key = signing_key("wJalrXUtnFEMI/K7MDENG/bPxRfiCYEXAMPLEKEY", "20130524", "us-east-1");
+        assert_eq!(
+            hex::encode(key),
+            "dbb893acc010964918f1fd433add87c70e8b0db6be30c1fbeafefa5ec6ba8378"
+        );
+    }
+
+    #[test]
+    fn canonical_uri_encodes_each_segment() {
+        assert_eq!(canonical_uri(""), "/");
+        assert_eq!(canonical_uri("/test file.txt"), "/test%20file.txt");
+        assert_eq!(canonical_uri("/a/b c/d"), "/a/b%20c/d");
+    }
+
+    #[test]
+    fn canonical_query_string_sorts_and_encodes_pairs() {
+        assert_eq!(
+            canonical_query_string("list-type=2&prefix=a b&delimiter=%2F"),
+            "delimiter=%252F&list-type=2&prefix=a%20b"
+        );
+        assert_eq!(canonical_query_string(""), "");
+    }
+
+    #[test]
+    fn payload_hash_is_unsigned_for_get_and_head() {
+        assert_eq!(payload_hash("GET", Some("ignored")), "UNSIGNED-PAYLOAD");
+        assert_eq!(payload_hash("HEAD", None), "UNSIGNED-PAYLOAD");
+        assert_eq!(payload_hash("GET", None), "UNSIGNED-PAYLOAD");
+    }
+
+    #[test]
+    fn payload_hash_hashes_body_for_other_methods() {
+        assert_eq!(
+            payload_hash("PUT", Some("abc")),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}